@@ -15,9 +15,21 @@ The `User` class deals with the (current) Api user.
 )]
 
 use crate::api::Api;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::error::Error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// A per-action rate limit (e.g. edits or uploads per time window) for a
+/// user group, as returned by `uiprop=ratelimits`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Number of actions allowed per `seconds`
+    pub hits: u64,
+    /// Length of the rate limit window, in seconds
+    pub seconds: u64,
+}
 
 /// `User` contains the login data for the `Api`
 #[derive(Debug, Default, Clone)]
@@ -47,15 +59,15 @@ impl User {
     /// Checks is the user has a spefic right (e.g. "bot", "autocinfirmed")
     pub fn has_right(&self, right: &str) -> Option<bool> {
         match &self.user_info {
-            Some(ui) => {
+            Some(ui) => Some(
                 ui["query"]["userinfo"]["rights"]
                     .as_array()
                     .unwrap_or(&vec![])
                     .iter()
                     .filter(|x| x.as_str().unwrap_or("") == right)
                     .count()
-                    > 0
-            }
+                    > 0,
+            ),
             None => None,
         }
     }
@@ -72,22 +84,22 @@ impl User {
 
     /// Checks if the user is allowed to edit
     pub fn can_edit(&self) -> Option<bool> {
-        self.has_right("edit")
+        self.can_perform("edit")
     }
 
     /// Checks if the user is allowed to create a page
     pub fn can_create_page(&self) -> Option<bool> {
-        self.has_right("createpage")
+        self.can_perform("createpage")
     }
 
     /// Checks if the user is allowed to upload a file
     pub fn can_upload(&self) -> Option<bool> {
-        self.has_right("upload")
+        self.can_perform("upload")
     }
 
     /// Checks if the user is allowed to move (rename) a page
     pub fn can_move(&self) -> Option<bool> {
-        self.has_right("move")
+        self.can_perform("move")
     }
 
     /// Checks if the user is allowed to patrol edits
@@ -95,24 +107,297 @@ impl User {
         self.has_right("patrol")
     }
 
+    /// Checks if the user is currently blocked
+    pub fn is_blocked(&self) -> Option<bool> {
+        match &self.user_info {
+            Some(ui) => Some(!ui["query"]["userinfo"]["blockid"].is_null()),
+            None => None,
+        }
+    }
+
+    /// Returns the reason given for the current block, if any
+    pub fn block_reason(&self) -> Option<String> {
+        self.user_info.as_ref()?["query"]["userinfo"]["blockreason"]
+            .as_str()
+            .map(|s| s.to_string())
+    }
+
+    /// Returns the expiry timestamp of the current block, if any
+    pub fn block_expiry(&self) -> Option<String> {
+        self.user_info.as_ref()?["query"]["userinfo"]["blockexpiry"]
+            .as_str()
+            .map(|s| s.to_string())
+    }
+
+    /// Returns the name of the admin who placed the current block, if any
+    pub fn blocked_by(&self) -> Option<String> {
+        self.user_info.as_ref()?["query"]["userinfo"]["blockedby"]
+            .as_str()
+            .map(|s| s.to_string())
+    }
+
+    /// Returns the ID of the current block, if any
+    pub fn block_id(&self) -> Option<u64> {
+        self.user_info.as_ref()?["query"]["userinfo"]["blockid"].as_u64()
+    }
+
+    /// Checks if the user has `right`, re-checking for an active block the
+    /// same way server-side permission checks do, so a blocked user never
+    /// reports a capability as available even if the raw right is present
+    fn can_perform(&self, right: &str) -> Option<bool> {
+        let has_right = self.has_right(right)?;
+        match self.is_blocked() {
+            Some(true) => Some(false),
+            _ => Some(has_right),
+        }
+    }
+
+    /// Returns `Ok(())` if the user can edit, or a descriptive error if the
+    /// `edit` right is missing or the user is currently blocked
+    pub fn require_can_edit(&self) -> Result<(), Box<dyn Error>> {
+        if self.is_blocked() == Some(true) {
+            return Err(match self.block_reason() {
+                Some(reason) => format!("user is blocked: {}", reason),
+                None => "user is blocked".to_string(),
+            }
+            .into());
+        }
+        match self.has_right("edit") {
+            Some(true) => Ok(()),
+            _ => Err("user does not have the edit right".into()),
+        }
+    }
+
+    /// Returns the most restrictive rate limit that applies to this user for
+    /// `action` (e.g. "edit", "upload"), picking the bucket with the largest
+    /// `seconds/hits` ratio (the longest required wait between actions)
+    /// across all of the user's groups
+    pub fn rate_limit(&self, action: &str) -> Option<RateLimit> {
+        self.user_info.as_ref()?["query"]["userinfo"]["ratelimits"][action]
+            .as_object()?
+            .values()
+            .filter_map(|v| {
+                Some(RateLimit {
+                    hits: v["hits"].as_u64()?,
+                    seconds: v["seconds"].as_u64()?,
+                })
+            })
+            .max_by(|a, b| {
+                let ratio = |r: &RateLimit| r.seconds as f64 / r.hits.max(1) as f64;
+                ratio(a)
+                    .partial_cmp(&ratio(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    /// Returns the user's edit rate limit, if any
+    pub fn edit_rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit("edit")
+    }
+
+    /// Returns the user's upload rate limit, if any
+    pub fn upload_rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit("upload")
+    }
+
+    /// Returns the minimum interval a bot should wait between edits to stay
+    /// within the user's edit rate limit
+    pub fn min_edit_interval(&self) -> Option<Duration> {
+        let rl = self.edit_rate_limit()?;
+        Some(Duration::from_secs_f64(
+            rl.seconds as f64 / rl.hits.max(1) as f64,
+        ))
+    }
+
     /// Loads the user info, which is stored in the object; returns Ok(()) if successful
     pub fn load_user_info(&mut self, api: &Api) -> Result<(), Box<dyn Error>> {
         match self.user_info {
             Some(_) => Ok(()),
-            None => {
-                let params: HashMap<String, String> = vec![
-                    ("action", "query"),
-                    ("meta", "userinfo"),
-                    ("uiprop", "blockinfo|groups|groupmemberships|implicitgroups|rights|options|ratelimits|realname|registrationdate|unreadcount|centralids|hasmsg"),
-                ]
-                .iter()
-                .map(|x| (x.0.to_string(), x.1.to_string()))
-                .collect();
-                let res = api.query_api_json(&params, "GET")?;
-                self.user_info = Some(res);
-                Ok(())
+            None => self.refresh_user_info(api),
+        }
+    }
+
+    /// Forcibly re-queries and replaces the cached user info, even if it was
+    /// already loaded. Use this in a long-running bot to pick up a new
+    /// `unreadcount`/`hasmsg` state that `load_user_info` would otherwise
+    /// short-circuit past.
+    pub fn refresh_user_info(&mut self, api: &Api) -> Result<(), Box<dyn Error>> {
+        let params: HashMap<String, String> = vec![
+            ("action", "query"),
+            ("meta", "userinfo"),
+            ("uiprop", "blockinfo|groups|groupmemberships|implicitgroups|rights|options|ratelimits|realname|registrationdate|unreadcount|centralids|hasmsg"),
+        ]
+        .iter()
+        .map(|x| (x.0.to_string(), x.1.to_string()))
+        .collect();
+        let res = api.query_api_json(&params, "GET")?;
+        self.user_info = Some(res);
+        Ok(())
+    }
+
+    /// Returns the number of unread notifications (talk page messages, Echo
+    /// alerts, etc.), if known
+    pub fn unread_notifications(&self) -> Option<u64> {
+        self.user_info.as_ref()?["query"]["userinfo"]["unreadcount"].as_u64()
+    }
+
+    /// Checks if the user has new messages on their talk page
+    pub fn has_new_messages(&self) -> Option<bool> {
+        let ui = self.user_info.as_ref()?;
+        Some(!ui["query"]["userinfo"]["messages"].is_null())
+    }
+
+    /// Fetches the structured Echo notification list via
+    /// `action=query&meta=notifications`
+    pub fn fetch_notifications(&self, api: &Api) -> Result<Value, Box<dyn Error>> {
+        let params: HashMap<String, String> = vec![("action", "query"), ("meta", "notifications")]
+            .iter()
+            .map(|x| (x.0.to_string(), x.1.to_string()))
+            .collect();
+        api.query_api_json(&params, "GET")
+    }
+
+    /// Returns a single user preference by name (e.g. `nickname`, `watchdefault`)
+    pub fn option(&self, name: &str) -> Option<&Value> {
+        self.user_info.as_ref()?["query"]["userinfo"]["options"].get(name)
+    }
+
+    /// Returns the full map of the user's preferences
+    pub fn options(&self) -> Option<&Map<String, Value>> {
+        self.user_info.as_ref()?["query"]["userinfo"]["options"].as_object()
+    }
+
+    /// Sets a single user preference via `action=options`, updating the
+    /// cached user info on success
+    pub fn set_option(&mut self, api: &mut Api, name: &str, value: &str) -> Result<(), Box<dyn Error>> {
+        self.set_options(api, &[(name, value)])
+    }
+
+    /// Sets multiple user preferences in a single `action=options` request,
+    /// then re-queries user info so the cache picks up the native JSON type
+    /// (number, bool, string) the API actually stores the new values as.
+    pub fn set_options(
+        &mut self,
+        api: &mut Api,
+        options: &[(&str, &str)],
+    ) -> Result<(), Box<dyn Error>> {
+        for (name, value) in options {
+            if name.contains('|') || value.contains('|') {
+                return Err(format!(
+                    "option name/value containing '|' is not supported: {}={}",
+                    name, value
+                )
+                .into());
             }
         }
+        let token = api.get_token("csrf")?;
+        let change = options
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>()
+            .join("|");
+        let params: HashMap<String, String> = vec![
+            ("action", "options"),
+            ("token", &token),
+            ("change", &change),
+        ]
+        .iter()
+        .map(|x| (x.0.to_string(), x.1.to_string()))
+        .collect();
+        api.post_query_api_json(&params)?;
+
+        self.refresh_user_info(api)
+    }
+
+    /// Returns the user's explicit group memberships (e.g. `sysop`, `bot`)
+    pub fn groups(&self) -> Option<Vec<String>> {
+        self.string_array("groups")
+    }
+
+    /// Returns the user's implicit (automatically granted) groups, e.g.
+    /// `*` or `user`
+    pub fn implicit_groups(&self) -> Option<Vec<String>> {
+        self.string_array("implicitgroups")
+    }
+
+    /// Returns the user's group memberships together with their expiry
+    /// timestamp (`None` for a permanent grant)
+    pub fn group_memberships(&self) -> Option<Vec<(String, Option<String>)>> {
+        Some(
+            self.user_info.as_ref()?["query"]["userinfo"]["groupmemberships"]
+                .as_array()?
+                .iter()
+                .filter_map(|gm| {
+                    let group = gm["group"].as_str()?.to_string();
+                    let expiry = gm["expiry"].as_str().map(|s| s.to_string());
+                    Some((group, expiry))
+                })
+                .collect(),
+        )
+    }
+
+    /// Checks if the user is a member of `name`, explicitly or implicitly
+    pub fn in_group(&self, name: &str) -> Option<bool> {
+        let explicit = self.groups()?;
+        let implicit = self.implicit_groups().unwrap_or_default();
+        Some(explicit.iter().any(|g| g == name) || implicit.iter().any(|g| g == name))
+    }
+
+    /// Returns the group memberships that have a temporary expiry, parsed
+    /// into a `SystemTime` so a maintenance bot can compare against
+    /// `SystemTime::now()` and warn before a temporary `sysop`/`bot` flag
+    /// lapses, rather than hand-parsing MediaWiki's timestamp itself.
+    /// Memberships whose expiry doesn't parse as a MediaWiki UTC timestamp
+    /// are skipped.
+    pub fn expiring_groups(&self) -> Vec<(String, SystemTime)> {
+        self.group_memberships()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(group, expiry)| Some((group, parse_mediawiki_timestamp(&expiry?)?)))
+            .collect()
+    }
+
+    /// Reads a `query.userinfo` field as an array of strings
+    fn string_array(&self, key: &str) -> Option<Vec<String>> {
+        Some(
+            self.user_info.as_ref()?["query"]["userinfo"][key]
+                .as_array()?
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+        )
+    }
+
+    /// Returns the `centralids` map (e.g. the `CentralAuth` global user id),
+    /// if known
+    pub fn central_ids(&self) -> Option<&Map<String, Value>> {
+        self.user_info.as_ref()?["query"]["userinfo"]["centralids"].as_object()
+    }
+
+    /// Returns the global (`CentralAuth`) user id, if known. Stable across
+    /// every wiki the account is attached to, unlike `user_id()`.
+    pub fn global_user_id(&self) -> Option<u64> {
+        self.central_ids()?.get("CentralAuth")?.as_u64()
+    }
+
+    /// Queries `action=query&meta=globaluserinfo&guiprop=merged` to list the
+    /// name of every wiki this SUL account is attached to
+    pub fn attached_wikis(&self, api: &Api) -> Result<Vec<String>, Box<dyn Error>> {
+        let params: HashMap<String, String> = vec![
+            ("action", "query"),
+            ("meta", "globaluserinfo"),
+            ("guiprop", "merged"),
+        ]
+        .iter()
+        .map(|x| (x.0.to_string(), x.1.to_string()))
+        .collect();
+        let res = api.query_api_json(&params, "GET")?;
+        Ok(res["query"]["globaluserinfo"]["merged"]
+            .as_array()
+            .unwrap_or(&vec![])
+            .iter()
+            .filter_map(|w| w["wiki"].as_str().map(|s| s.to_string()))
+            .collect())
     }
 
     /// Returns Ok(user name) (None if not logged in)
@@ -143,6 +428,149 @@ impl User {
         }
         Ok(())
     }
+
+    /// Builds a logged-in `User` from an OAuth 2.0 access token, e.g. one
+    /// issued to an owner-only consumer. Calls `action=query&meta=userinfo`
+    /// with the token as a Bearer `Authorization` header; this can't go
+    /// through `Api::query_api_json` since that sends no such header, so a
+    /// plain request is built directly against `api.api_url()`, reusing
+    /// `api`'s shared client and user agent like every other request path.
+    pub fn from_oauth2_token(api: &Api, access_token: &str) -> Result<User, Box<dyn Error>> {
+        let url = Url::parse_with_params(
+            api.api_url(),
+            &[("action", "query"), ("format", "json"), ("meta", "userinfo")],
+        )?;
+        let res: Value = api
+            .client()
+            .get(url)
+            .header(reqwest::header::USER_AGENT, api.user_agent_full())
+            .bearer_auth(access_token)
+            .send()?
+            .json()?;
+
+        let mut user = User::new();
+        user.set_from_oauth2_userinfo(&res)?;
+        Ok(user)
+    }
+
+    /// Fills the user's name/id/login state from an `action=query&meta=userinfo`
+    /// response and caches it as `user_info`
+    fn set_from_oauth2_userinfo(&mut self, res: &Value) -> Result<(), Box<dyn Error>> {
+        let userinfo = &res["query"]["userinfo"];
+        match userinfo["name"].as_str() {
+            Some(s) => self.lgusername = Some(s.to_string()),
+            None => return Err("No user name in userinfo response".into()),
+        }
+        match userinfo["id"].as_u64() {
+            Some(u) => self.lguserid = Some(u),
+            None => return Err("No user id in userinfo response".into()),
+        }
+        self.is_logged_in = true;
+        self.user_info = Some(res.clone());
+        Ok(())
+    }
+}
+
+/// Helper for the OAuth 2.0 authorization-code flow: builds the authorization
+/// URL to send the account owner to, then exchanges the code MediaWiki
+/// redirects back with for an access token. Owner-only consumers can skip
+/// this altogether and call `User::from_oauth2_token` with a token issued
+/// directly on the consumer's "manage OAuth consumers" page.
+#[derive(Debug, Clone)]
+pub struct OAuth2Login {
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+}
+
+impl OAuth2Login {
+    /// Creates a new OAuth 2.0 login helper for the given consumer credentials
+    pub fn new(client_id: &str, client_secret: &str, redirect_uri: &str) -> OAuth2Login {
+        OAuth2Login {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+        }
+    }
+
+    /// Builds the URL the caller should open in a browser so the account
+    /// owner can authorize the consumer. `client_id`/`redirect_uri` are
+    /// percent-encoded as query parameters, so a `redirect_uri` with its own
+    /// query string round-trips intact.
+    pub fn authorize_url(&self, api: &Api) -> Result<String, Box<dyn Error>> {
+        let base = Url::parse(api.api_url())?.join("rest.php/oauth2/authorize")?;
+        let url = Url::parse_with_params(
+            base.as_str(),
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+            ],
+        )?;
+        Ok(url.to_string())
+    }
+
+    /// Exchanges an authorization `code` (returned to `redirect_uri`) for an
+    /// access token, then builds a logged-in `User` from it. The token
+    /// request body is sent as a form (via `reqwest`'s `form`), which
+    /// percent-encodes every field for us, over `api`'s shared client and
+    /// user agent.
+    pub fn exchange_code(&self, api: &Api, code: &str) -> Result<User, Box<dyn Error>> {
+        let token_url = Url::parse(api.api_url())?.join("rest.php/oauth2/access_token")?;
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+        ];
+        let res: Value = api
+            .client()
+            .post(token_url)
+            .header(reqwest::header::USER_AGENT, api.user_agent_full())
+            .form(&params)
+            .send()?
+            .json()?;
+        let access_token = res["access_token"]
+            .as_str()
+            .ok_or("No access_token in OAuth token response")?;
+        User::from_oauth2_token(api, access_token)
+    }
+}
+
+/// Parses a MediaWiki-style ISO-8601 UTC timestamp (`YYYY-MM-DDTHH:MM:SSZ`,
+/// as returned for e.g. `groupmemberships` expiry) into a `SystemTime`.
+/// This workspace has no date/time crate dependency, so parsing is done by
+/// hand rather than pulling one in for a single format.
+fn parse_mediawiki_timestamp(s: &str) -> Option<SystemTime> {
+    let s = s.strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+
+    let mut date_parts = date.split('-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    let mut time_parts = time.split(':');
+    let hour: i64 = time_parts.next()?.parse().ok()?;
+    let minute: i64 = time_parts.next()?.parse().ok()?;
+    let second: i64 = time_parts.next()?.parse().ok()?;
+
+    // Howard Hinnant's days-from-civil algorithm: proleptic Gregorian date to
+    // days since the 1970-01-01 epoch.
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days_since_epoch = era * 146_097 + doe - 719_468;
+
+    let seconds_since_epoch = days_since_epoch * 86_400 + hour * 3_600 + minute * 60 + second;
+    if seconds_since_epoch < 0 {
+        return None;
+    }
+    Some(UNIX_EPOCH + Duration::from_secs(seconds_since_epoch as u64))
 }
 
 #[cfg(test)]
@@ -175,6 +603,161 @@ mod tests {
         assert_eq!(user.user_id(), user_id);
     }
 
+    #[test]
+    fn user_blocked_denies_edit_even_with_right() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{
+            "rights":["edit","upload"],
+            "blockid":1,
+            "blockreason":"socking",
+            "blockexpiry":"infinite",
+            "blockedby":"Admin"
+        }}}));
+        assert_eq!(user.is_blocked(), Some(true));
+        assert_eq!(user.can_edit(), Some(false));
+        assert_eq!(user.can_upload(), Some(false));
+        assert_eq!(user.block_reason(), Some("socking".to_string()));
+        assert_eq!(user.blocked_by(), Some("Admin".to_string()));
+        assert_eq!(user.block_id(), Some(1));
+        assert!(user.require_can_edit().is_err());
+    }
+
+    #[test]
+    fn user_not_blocked_keeps_right() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{"rights":["edit"]}}}));
+        assert_eq!(user.is_blocked(), Some(false));
+        assert_eq!(user.can_edit(), Some(true));
+        assert!(user.require_can_edit().is_ok());
+    }
+
+    #[test]
+    fn user_from_oauth2_userinfo() {
+        let mut user = User::new();
+        let res = json!({"query":{"userinfo":{"id":987,"name":"OAuth user"}}});
+        user.set_from_oauth2_userinfo(&res).unwrap();
+        assert!(user.logged_in());
+        assert_eq!(user.user_name(), Some("OAuth user".to_string()));
+        assert_eq!(user.user_id(), Some(987));
+    }
+
+    #[test]
+    fn oauth2_authorize_url_percent_encodes_redirect_uri() {
+        let login = OAuth2Login::new(
+            "myclientid",
+            "myclientsecret",
+            "https://example.org/callback?a=1&b=2",
+        );
+        let url = login.authorize_url(wd_api()).unwrap();
+        assert!(url.contains("client_id=myclientid"));
+        assert!(url.contains("redirect_uri=https%3A%2F%2Fexample.org%2Fcallback%3Fa%3D1%26b%3D2"));
+    }
+
+    #[test]
+    fn from_oauth2_token_rejects_invalid_token() {
+        let result = User::from_oauth2_token(wd_api(), "not-a-real-token");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn oauth2_exchange_code_rejects_invalid_code() {
+        let login = OAuth2Login::new("myclientid", "myclientsecret", "https://example.org/callback");
+        let result = login.exchange_code(wd_api(), "not-a-real-code");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn user_edit_rate_limit_picks_most_restrictive_group() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{"ratelimits":{
+            "edit":{
+                "user":{"hits":90,"seconds":60},
+                "newbie":{"hits":8,"seconds":60}
+            }
+        }}}}));
+        let rl = user.edit_rate_limit().unwrap();
+        assert_eq!(rl, RateLimit { hits: 8, seconds: 60 });
+        assert_eq!(user.min_edit_interval(), Some(Duration::from_millis(7500)));
+    }
+
+    #[test]
+    fn user_rate_limit_absent_is_none() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{}}}));
+        assert_eq!(user.edit_rate_limit(), None);
+        assert_eq!(user.min_edit_interval(), None);
+    }
+
+    #[test]
+    fn user_notification_state() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{"unreadcount":3,"messages":true}}}));
+        assert_eq!(user.unread_notifications(), Some(3));
+        assert_eq!(user.has_new_messages(), Some(true));
+
+        user.user_info = Some(json!({"query":{"userinfo":{}}}));
+        assert_eq!(user.unread_notifications(), None);
+        assert_eq!(user.has_new_messages(), Some(false));
+    }
+
+    #[test]
+    fn user_reads_cached_options() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{"options":{
+            "nickname":"Bot",
+            "watchdefault":1
+        }}}}));
+        assert_eq!(user.option("nickname"), Some(&json!("Bot")));
+        assert_eq!(user.option("watchdefault"), Some(&json!(1)));
+        assert_eq!(user.option("nonexistent"), None);
+        assert_eq!(user.options().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn user_global_user_id_from_central_ids() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{"centralids":{
+            "CentralAuth":123456,
+            "local":12
+        }}}}));
+        assert_eq!(user.global_user_id(), Some(123456));
+    }
+
+    #[test]
+    fn user_group_membership_with_expiry() {
+        let mut user = User::new();
+        user.user_info = Some(json!({"query":{"userinfo":{
+            "groups":["sysop","user"],
+            "implicitgroups":["*","user"],
+            "groupmemberships":[
+                {"group":"sysop","expiry":"2026-08-01T00:00:00Z"},
+                {"group":"user","expiry":null}
+            ]
+        }}}));
+        assert_eq!(user.in_group("sysop"), Some(true));
+        assert_eq!(user.in_group("bot"), Some(false));
+        assert_eq!(
+            user.expiring_groups(),
+            vec![(
+                "sysop".to_string(),
+                parse_mediawiki_timestamp("2026-08-01T00:00:00Z").unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn parses_mediawiki_timestamp() {
+        assert_eq!(
+            parse_mediawiki_timestamp("1970-01-01T00:00:00Z"),
+            Some(UNIX_EPOCH)
+        );
+        assert_eq!(
+            parse_mediawiki_timestamp("2026-08-01T12:30:15Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1785587415))
+        );
+        assert_eq!(parse_mediawiki_timestamp("not a timestamp"), None);
+    }
+
     #[test]
     fn user_rights() {
         let mut user = User::new();